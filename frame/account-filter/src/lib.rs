@@ -7,8 +7,73 @@
 
 pub use pallet::*;
 
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Identifies one of up to 64 call groups that an allow-listed account can be scoped to.
+pub type GroupId = u8;
+
+/// The set of [`GroupId`]s an allow-listed account is permitted to submit, encoded as a bitmask
+/// so membership checks and unions stay O(1) regardless of how many groups are defined.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct CallGroups(u64);
+
+impl CallGroups {
+    /// No groups granted.
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    /// Every group granted, matching the original all-or-nothing behaviour.
+    pub const fn all() -> Self {
+        Self(u64::MAX)
+    }
+
+    /// Builds a set out of individual group ids, ignoring any id outside of the `0..64` range.
+    pub fn from_groups(groups: &[GroupId]) -> Self {
+        groups
+            .iter()
+            .fold(Self::none(), |set, group| set.inserted(*group))
+    }
+
+    /// Returns a copy of `self` with `group` added, if it is in range.
+    pub fn inserted(self, group: GroupId) -> Self {
+        match 1u64.checked_shl(group as u32) {
+            Some(mask) => Self(self.0 | mask),
+            None => self,
+        }
+    }
+
+    /// Whether `group` is part of this set.
+    pub fn contains(&self, group: GroupId) -> bool {
+        matches!(1u64.checked_shl(group as u32), Some(mask) if self.0 & mask != 0)
+    }
+
+    /// The set containing every group present in either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
 pub trait BlockCallMatcher<T: Config> {
-    fn matches(call: &<T as frame_system::Config>::RuntimeCall) -> bool;
+    /// Returns the call group `call` belongs to, or `None` if it is not subject to the
+    /// allow-list at all.
+    fn group(call: &<T as frame_system::Config>::RuntimeCall) -> Option<GroupId>;
+}
+
+/// An external source of truth for whether an account has passed whatever verification a
+/// deployment requires (e.g. a KYC/identity pallet) before it may be admitted to the allow-list.
+pub trait VerifiedAccount<AccountId> {
+    /// Whether `who` has passed verification and may be admitted to the allow-list.
+    fn is_verified(who: &AccountId) -> bool;
+}
+
+/// No-op implementation that verifies every account, so existing runtimes that do not wire up
+/// an identity provider keep today's behaviour.
+impl<AccountId> VerifiedAccount<AccountId> for () {
+    fn is_verified(_who: &AccountId) -> bool {
+        true
+    }
 }
 
 #[frame_support::pallet]
@@ -16,13 +81,14 @@ pub mod pallet {
 
     use super::*;
     use frame_support::dispatch::DispatchInfo;
+    use frame_support::traits::StorageVersion;
     use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
     use frame_system::pallet_prelude::*;
     use parity_scale_codec::{Decode, Encode};
     use scale_info::TypeInfo;
     use sp_runtime::Percent;
     use sp_runtime::{
-        traits::{DispatchInfoOf, Dispatchable, SignedExtension},
+        traits::{DispatchInfoOf, Dispatchable, SignedExtension, UniqueSaturatedInto, Zero},
         transaction_validity::{
             InvalidTransaction, TransactionLongevity, TransactionValidity,
             TransactionValidityError, ValidTransaction,
@@ -38,23 +104,100 @@ pub mod pallet {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type CallsToFilter: BlockCallMatcher<Self>;
         type VotesToAllow: Get<Percent>;
+        type VotesToRemove: Get<Percent>;
+        /// How long a vote stays valid for before it is considered stale and no longer counts
+        /// towards the tally.
+        type VoteTtl: Get<BlockNumberFor<Self>>;
+        /// The allow-list can never shrink below this many accounts, so that it can never be
+        /// emptied and brick the chain.
+        type MinAllowedAccounts: Get<u32>;
+        /// External source of truth (e.g. a KYC/identity pallet) consulted before an account that
+        /// has crossed the vote threshold is actually admitted to the allow-list.
+        type IdentityProvider: VerifiedAccount<Self::AccountId>;
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
-    // The allowed accounts.
+    /// Everything recorded about an account at the moment it is admitted to the allow-list: the
+    /// call groups it was granted, when it was admitted, and how many referrers vouched for it.
+    /// Kept alongside the entry so `AllowAccount::validate` can derive a reputation-weighted
+    /// priority without an extra storage read.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    pub struct AllowedAccountMeta<BlockNumber> {
+        pub groups: CallGroups,
+        pub admitted_at: BlockNumber,
+        pub referrers: u32,
+    }
+
+    // The allowed accounts, each scoped to the call groups it was admitted for, alongside the
+    // admission block number and referrer count used to compute transaction priority.
+    //
+    // Pinned to its original identifier via `storage_prefix`: this item started out as
+    // `AllowedAccounts: StorageMap<AccountId, ()>` and was renamed to `AllowedAccountsList`
+    // to make room for the O(1) counter below, but a Rust-level rename must never move the
+    // on-chain trie key a live chain already has data under.
+    #[pallet::storage]
+    #[pallet::storage_prefix = "AllowedAccounts"]
+    #[pallet::getter(fn allowed_accounts_list)]
+    pub type AllowedAccountsList<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, AllowedAccountMeta<BlockNumberFor<T>>>;
+
+    // O(1) mirror of `AllowedAccountsList::iter().count()`, kept in sync on every insert/remove
+    // so the tally in `vote_for_account`/`vote_to_remove_account` does not need a full scan.
+    //
+    // Named distinctly from `AllowedAccountsList` above (rather than reusing the `AllowedAccounts`
+    // identifier that item's `storage_prefix` keeps pinned to) so the two can never be confused.
     #[pallet::storage]
     #[pallet::getter(fn allowed_accounts)]
-    pub type AllowedAccounts<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+    pub type AllowedAccountsCount<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+    /// A single cast vote: when it was cast, used to expire it after `T::VoteTtl`, and which call
+    /// groups the referrer is vouching for the referee to receive.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+    pub struct VoteRecord<BlockNumber> {
+        pub cast_at: BlockNumber,
+        pub groups: CallGroups,
+    }
 
     // Voting process for the allow-list.
-    // The key is the account that is being voted for. The value is the account that is voting for.
+    // The key is the account that is being voted for. The value records when the vote was cast
+    // and which call groups it is vouching for.
     #[pallet::storage]
     #[pallet::getter(fn votes)]
-    pub type Votes<T: Config> =
+    pub type Votes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        VoteRecord<BlockNumberFor<T>>,
+    >;
+
+    // O(1) mirror of `Votes::iter_prefix(referee).count()`, kept in sync on every insert/remove
+    // from `Votes` (including TTL expiry) so the tally does not need a full prefix scan.
+    #[pallet::storage]
+    #[pallet::getter(fn votes_for_account)]
+    pub type VotesCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u128>;
+
+    // Voting process for removing an account from the allow-list.
+    // The key is the account that is being voted to be removed. The value is the account that is
+    // voting for the removal.
+    #[pallet::storage]
+    #[pallet::getter(fn removal_votes)]
+    pub type RemovalVotes<T: Config> =
         StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
 
+    // O(1) mirror of `RemovalVotes::iter_prefix(account).count()`, kept in sync on every
+    // insert/drain from `RemovalVotes` so the tally in `vote_to_remove_account` does not need a
+    // full prefix scan.
+    #[pallet::storage]
+    #[pallet::getter(fn removal_votes_for_account)]
+    pub type RemovalVotesCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u128>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -67,6 +210,21 @@ pub mod pallet {
             referrer: T::AccountId,
             referee: T::AccountId,
         },
+        // When an allowed account casts a vote to remove another allowed account.
+        AccountRemovalVoted {
+            referrer: T::AccountId,
+            referee: T::AccountId,
+        },
+        // When an account is dropped from the allow-list.
+        AccountRemoved {
+            account: T::AccountId,
+            voted_for: Vec<T::AccountId>,
+        },
+        // When an account has crossed the vote threshold but `T::IdentityProvider` has not yet
+        // verified it, so admission is held back.
+        AccountAdmissionPendingVerification {
+            account: T::AccountId,
+        },
     }
 
     #[pallet::error]
@@ -74,14 +232,24 @@ pub mod pallet {
         AlreadyAllowed,
         DuplicateVote,
         NotAllowedToVote,
+        NotAllowed,
+        DuplicateRemovalVote,
+        BelowMinimumQuorum,
+        InvalidCallGroup,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        // Opportunistically prune expired votes with whatever idle weight is left in the block,
+        // so `Votes` does not grow without bound even if nobody calls `vote_for_account` again.
+        fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::prune_expired_votes(now, remaining_weight)
+        }
+    }
 
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
-        pub allowed_accounts: Vec<(T::AccountId, ())>,
+        pub allowed_accounts: Vec<(T::AccountId, CallGroups)>,
     }
 
     #[cfg(feature = "std")]
@@ -102,40 +270,232 @@ pub mod pallet {
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Add a new account to the allow-list.
+        /// Add a new account to the allow-list with full access to every filtered call group.
         /// Can only be called by the defined origin.
+        ///
+        /// Kept at its original 2-argument signature, always granting every call group, for
+        /// backwards compatibility with existing callers. To vouch for an account scoped to a
+        /// subset of call groups, use [`Self::vote_for_account_in_groups`] instead.
         #[pallet::weight(0)]
         #[pallet::call_index(0)]
         pub fn vote_for_account(
             origin: OriginFor<T>,
             new_account: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            Self::do_vote_for_account(origin, new_account, CallGroups::all())
+        }
+
+        /// Vote to remove an already allowed account from the allow-list.
+        /// Can only be called by an account that is itself allowed.
+        #[pallet::weight(0)]
+        #[pallet::call_index(1)]
+        pub fn vote_to_remove_account(
+            origin: OriginFor<T>,
+            account_to_remove: T::AccountId,
         ) -> DispatchResultWithPostInfo {
             let account = ensure_signed(origin)?;
             ensure!(
-                <AllowedAccounts<T>>::contains_key(&account),
+                <AllowedAccountsList<T>>::contains_key(&account),
                 Error::<T>::NotAllowedToVote
             );
             ensure!(
-                !<AllowedAccounts<T>>::contains_key(&new_account),
+                <AllowedAccountsList<T>>::contains_key(&account_to_remove),
+                Error::<T>::NotAllowed
+            );
+            ensure!(
+                !<RemovalVotes<T>>::contains_key(&account_to_remove, &account),
+                Error::<T>::DuplicateRemovalVote
+            );
+
+            let allowed_count = AllowedAccountsCount::<T>::get();
+
+            // Check if the account has enough removal votes to be dropped from the allow-list.
+            let votes_for = RemovalVotesCount::<T>::get(&account_to_remove).unwrap_or(0) + 1;
+            let percent = Percent::from_rational(votes_for, allowed_count);
+
+            if percent >= T::VotesToRemove::get() {
+                // Enough votes to cross the threshold: only the actual removal is blocked once
+                // the list is down at the floor, so the list can never be emptied and bricked.
+                // Recording votes below the floor is still allowed, it simply cannot complete.
+                ensure!(
+                    allowed_count > T::MinAllowedAccounts::get() as u128,
+                    Error::<T>::BelowMinimumQuorum
+                );
+
+                // Enough votes to remove the account from the allow-list.
+                Self::remove_allowed_account(&account_to_remove);
+                let voted_for = Self::drain_removal_votes(&account_to_remove)
+                    .into_iter()
+                    .chain(sp_std::iter::once(account.clone()))
+                    .collect();
+                let _ = Self::drain_votes(&account_to_remove);
+
+                Self::deposit_event(Event::AccountRemovalVoted {
+                    referrer: account,
+                    referee: account_to_remove.clone(),
+                });
+                Self::deposit_event(Event::AccountRemoved {
+                    account: account_to_remove,
+                    voted_for,
+                });
+            } else {
+                // Vote to remove the account.
+                Self::insert_removal_vote(&account_to_remove, &account);
+                Self::deposit_event(Event::AccountRemovalVoted {
+                    referrer: account,
+                    referee: account_to_remove,
+                });
+            }
+
+            Ok(().into())
+        }
+
+        /// Vote to add `new_account` to the allow-list, scoped to only the given call groups
+        /// instead of granting full access. The final set granted on admission is the union of
+        /// every vouching referrer's requested groups.
+        #[pallet::weight(0)]
+        #[pallet::call_index(2)]
+        pub fn vote_for_account_in_groups(
+            origin: OriginFor<T>,
+            new_account: T::AccountId,
+            groups: Vec<GroupId>,
+        ) -> DispatchResultWithPostInfo {
+            ensure!(
+                groups.iter().all(|group| *group < 64),
+                Error::<T>::InvalidCallGroup
+            );
+            Self::do_vote_for_account(origin, new_account, CallGroups::from_groups(&groups))
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn initialize_allowed_accounts(allowed_accounts: &[(T::AccountId, CallGroups)]) {
+            for (account, groups) in allowed_accounts.iter() {
+                // Genesis accounts were not voted in by any referrer.
+                Self::add_allowed_account(account, *groups, Zero::zero(), 0);
+            }
+        }
+
+        /// Insert `account` into the allow-list, keeping the `AllowedAccountsCount` O(1) counter
+        /// in sync.
+        fn add_allowed_account(
+            account: &T::AccountId,
+            groups: CallGroups,
+            admitted_at: BlockNumberFor<T>,
+            referrers: u32,
+        ) {
+            AllowedAccountsList::<T>::insert(
+                account,
+                AllowedAccountMeta {
+                    groups,
+                    admitted_at,
+                    referrers,
+                },
+            );
+            AllowedAccountsCount::<T>::mutate(|count| *count = count.saturating_add(1));
+        }
+
+        /// Drop `account` from the allow-list, keeping the `AllowedAccountsCount` O(1) counter
+        /// in sync.
+        fn remove_allowed_account(account: &T::AccountId) {
+            AllowedAccountsList::<T>::remove(account);
+            AllowedAccountsCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+        }
+
+        /// Record a vote for `referee` by `referrer`, keeping the `VotesCount` O(1) tally in
+        /// sync.
+        fn insert_vote(
+            referee: &T::AccountId,
+            referrer: &T::AccountId,
+            record: VoteRecord<BlockNumberFor<T>>,
+        ) {
+            Votes::<T>::insert(referee, referrer, record);
+            VotesCount::<T>::mutate(referee, |count| *count = Some(count.unwrap_or(0) + 1));
+        }
+
+        /// Drop a single vote, keeping the `VotesCount` O(1) tally in sync.
+        fn remove_vote(referee: &T::AccountId, referrer: &T::AccountId) {
+            if Votes::<T>::take(referee, referrer).is_some() {
+                VotesCount::<T>::mutate_exists(referee, |count| {
+                    let remaining = count.unwrap_or(1).saturating_sub(1);
+                    *count = (remaining > 0).then_some(remaining);
+                });
+            }
+        }
+
+        /// Drain every vote cast for `referee`, keeping the `VotesCount` O(1) tally in sync.
+        fn drain_votes(
+            referee: &T::AccountId,
+        ) -> Vec<(T::AccountId, VoteRecord<BlockNumberFor<T>>)> {
+            let records = Votes::<T>::drain_prefix(referee).collect();
+            VotesCount::<T>::remove(referee);
+            records
+        }
+
+        /// Record a removal vote for `account_to_remove` by `referrer`, keeping the
+        /// `RemovalVotesCount` O(1) tally in sync.
+        fn insert_removal_vote(account_to_remove: &T::AccountId, referrer: &T::AccountId) {
+            RemovalVotes::<T>::insert(account_to_remove, referrer, ());
+            RemovalVotesCount::<T>::mutate(account_to_remove, |count| {
+                *count = Some(count.unwrap_or(0) + 1)
+            });
+        }
+
+        /// Drain every removal vote cast for `account_to_remove`, keeping the
+        /// `RemovalVotesCount` O(1) tally in sync.
+        fn drain_removal_votes(account_to_remove: &T::AccountId) -> Vec<T::AccountId> {
+            let voters = RemovalVotes::<T>::drain_prefix(account_to_remove)
+                .map(|(voter, ())| voter)
+                .collect();
+            RemovalVotesCount::<T>::remove(account_to_remove);
+            voters
+        }
+
+        fn do_vote_for_account(
+            origin: OriginFor<T>,
+            new_account: T::AccountId,
+            groups: CallGroups,
+        ) -> DispatchResultWithPostInfo {
+            let account = ensure_signed(origin)?;
+            ensure!(
+                <AllowedAccountsList<T>>::contains_key(&account),
+                Error::<T>::NotAllowedToVote
+            );
+            ensure!(
+                !<AllowedAccountsList<T>>::contains_key(&new_account),
                 Error::<T>::AlreadyAllowed
             );
+
+            // Lazily drop any of this referee's votes that have aged out, so an expired vote
+            // never counts towards the tally and does not block the same referrer from
+            // revoting.
+            let now = <frame_system::Pallet<T>>::block_number();
+            Self::prune_votes_for(&new_account, now);
+
             ensure!(
                 !<Votes<T>>::contains_key(&new_account, &account),
                 Error::<T>::DuplicateVote
             );
 
             // Check if the new account has enough votes to be added to the allow-list.
-            let votes_for = Votes::<T>::iter_prefix(&new_account).count() + 1;
-            let votes_required = AllowedAccounts::<T>::iter().count();
+            // Only votes still within the TTL window were left standing by the prune above.
+            let votes_for = VotesCount::<T>::get(&new_account).unwrap_or(0) + 1;
+            let votes_required = AllowedAccountsCount::<T>::get();
             let percent = Percent::from_rational(votes_for, votes_required);
 
-            if percent >= T::VotesToAllow::get() {
-                // Enough votes to add the new account to the allow-list.
-                <AllowedAccounts<T>>::insert(&new_account, ());
-                let voted_for = <Votes<T>>::drain_prefix(&new_account)
+            if percent >= T::VotesToAllow::get() && T::IdentityProvider::is_verified(&new_account) {
+                // Enough votes to add the new account to the allow-list, with the union of every
+                // referrer's (plus this vote's own) requested call groups.
+                let records = Self::drain_votes(&new_account);
+                let granted = records
+                    .iter()
+                    .fold(groups, |acc, (_, record)| acc.union(&record.groups));
+                let voted_for: Vec<_> = records
+                    .into_iter()
                     .map(|(k, _)| k)
                     .chain(sp_std::iter::once(account.clone()))
                     .collect();
+                Self::add_allowed_account(&new_account, granted, now, voted_for.len() as u32);
 
                 Self::deposit_event(Event::AccountVoted {
                     referrer: account,
@@ -147,26 +507,240 @@ pub mod pallet {
                 });
             } else {
                 // Vote for the new account.
-                <Votes<T>>::insert(&new_account, &account, ());
+                Self::insert_vote(
+                    &new_account,
+                    &account,
+                    VoteRecord {
+                        cast_at: now,
+                        groups,
+                    },
+                );
                 Self::deposit_event(Event::AccountVoted {
                     referrer: account,
-                    referee: new_account,
+                    referee: new_account.clone(),
                 });
+                if percent >= T::VotesToAllow::get() {
+                    // The vote tally alone would have been enough, but the account has not
+                    // passed external verification yet, so admission is held back.
+                    Self::deposit_event(Event::AccountAdmissionPendingVerification {
+                        account: new_account,
+                    });
+                }
             }
 
             Ok(().into())
         }
+
+        /// Drop every vote cast for `referee` that is older than `T::VoteTtl`.
+        fn prune_votes_for(referee: &T::AccountId, now: BlockNumberFor<T>) {
+            let cutoff = now.saturating_sub(T::VoteTtl::get());
+            let stale: Vec<_> = Votes::<T>::iter_prefix(referee)
+                .filter(|(_, record)| record.cast_at < cutoff)
+                .map(|(referrer, _)| referrer)
+                .collect();
+            for referrer in stale {
+                Self::remove_vote(referee, &referrer);
+            }
+        }
+
+        /// Walk the whole `Votes` map and drop expired entries, stopping once `remaining_weight`
+        /// is exhausted. Returns the weight actually consumed.
+        fn prune_expired_votes(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            let db_weight = T::DbWeight::get();
+            // Each removal touches both `Votes` and the `VotesCount` tally, and adds the
+            // entry's keys and value to the proof.
+            let entry_proof_size = 2 * T::AccountId::max_encoded_len()
+                + VoteRecord::<BlockNumberFor<T>>::max_encoded_len();
+            let removal_weight = db_weight
+                .reads_writes(2, 2)
+                .saturating_add(Weight::from_parts(0, entry_proof_size as u64));
+            let mut consumed = Weight::zero();
+            let cutoff = now.saturating_sub(T::VoteTtl::get());
+
+            let mut stale = Vec::new();
+            for (referee, referrer, record) in Votes::<T>::iter() {
+                let projected = consumed.saturating_add(removal_weight);
+                if projected.ref_time() > remaining_weight.ref_time()
+                    || projected.proof_size() > remaining_weight.proof_size()
+                {
+                    break;
+                }
+                consumed = consumed.saturating_add(db_weight.reads(1));
+                if record.cast_at < cutoff {
+                    stale.push((referee, referrer));
+                    consumed = consumed.saturating_add(removal_weight);
+                }
+            }
+
+            for (referee, referrer) in stale {
+                Self::remove_vote(&referee, &referrer);
+            }
+
+            consumed
+        }
     }
 
-    impl<T: Config> Pallet<T> {
-        fn initialize_allowed_accounts(allowed_accounts: &[(T::AccountId, ())]) {
-            if !allowed_accounts.is_empty() {
-                for (account, extrinsics) in allowed_accounts.iter() {
-                    <AllowedAccounts<T>>::insert(account, extrinsics);
+    /// Storage migrations for this pallet.
+    pub mod migrations {
+        use super::*;
+        use frame_support::storage_alias;
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade};
+        use sp_std::collections::btree_map::BTreeMap;
+
+        /// The true pre-chunk0-4 shapes of this pallet's storage, as they exist on any chain that
+        /// was live before the `CallGroups`/`VoteRecord` value types and the O(1) tally counters
+        /// were introduced. Every shape change up to and including chunk0-4 landed without its
+        /// own `StorageVersion`, so `MigrateToV1` treats this whole pre-existing history as a
+        /// single logical generation and migrates straight from it.
+        mod v0 {
+            use super::*;
+
+            /// `AllowedAccounts` before call groups existed: membership only, no scoping.
+            #[storage_alias]
+            pub(super) type AllowedAccounts<T: Config> =
+                StorageMap<Pallet<T>, Blake2_128Concat, <T as frame_system::Config>::AccountId, ()>;
+
+            /// `Votes` before a cast-at block number or call groups were recorded: a cast vote
+            /// was just a key's presence.
+            #[storage_alias]
+            pub(super) type Votes<T: Config> = StorageDoubleMap<
+                Pallet<T>,
+                Blake2_128Concat,
+                <T as frame_system::Config>::AccountId,
+                Blake2_128Concat,
+                <T as frame_system::Config>::AccountId,
+                (),
+            >;
+        }
+
+        /// The V1 shape of `AllowedAccountsList`: a bare [`CallGroups`] rather than the richer
+        /// [`AllowedAccountMeta`] introduced in V2. Named `AllowedAccounts` to match the
+        /// `storage_prefix` pinned on the real item rather than its current Rust-level name,
+        /// since `#[storage_alias]` hashes the alias's own name (not just its path) into the
+        /// trie key — this is the exact key the map has held since before the chunk0-4 rename.
+        /// Written here by [`MigrateToV1`] and drained again by [`MigrateToV2`].
+        #[storage_alias]
+        type AllowedAccounts<T: Config> = StorageMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as frame_system::Config>::AccountId,
+            CallGroups,
+        >;
+
+        /// Migrates the true pre-existing (`v0`) storage into the V1 shapes — `CallGroups`-scoped
+        /// allow-list entries and `VoteRecord`-shaped votes — backfilling the `AllowedAccountsCount`
+        /// and `VotesCount` O(1) tally counters introduced alongside them.
+        ///
+        /// Pre-existing allow-listed accounts had no group scoping, so they backfill to
+        /// [`CallGroups::all`] to preserve the original all-or-nothing behaviour. Pre-existing
+        /// votes had no cast-at block recorded, so they backfill to the current block rather than
+        /// `0`, which would make them expire immediately under the TTL introduced later.
+        pub struct MigrateToV1<T>(PhantomData<T>);
+
+        impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+            fn on_runtime_upgrade() -> Weight {
+                let onchain = Pallet::<T>::on_chain_storage_version();
+                if onchain >= 1 {
+                    return T::DbWeight::get().reads(1);
                 }
+
+                let now = frame_system::Pallet::<T>::block_number();
+
+                let accounts: Vec<_> = v0::AllowedAccounts::<T>::drain().map(|(a, ())| a).collect();
+                let allowed_count = accounts.len() as u64;
+                for account in accounts {
+                    AllowedAccounts::<T>::insert(account, CallGroups::all());
+                }
+                AllowedAccountsCount::<T>::put(allowed_count as u128);
+
+                let votes: Vec<_> = v0::Votes::<T>::drain().map(|(r, f, ())| (r, f)).collect();
+                let mut tallies: BTreeMap<T::AccountId, u128> = BTreeMap::new();
+                for (referee, referrer) in &votes {
+                    Votes::<T>::insert(
+                        referee,
+                        referrer,
+                        VoteRecord {
+                            cast_at: now,
+                            groups: CallGroups::all(),
+                        },
+                    );
+                    *tallies.entry(referee.clone()).or_default() += 1;
+                }
+                let vote_count = votes.len() as u64;
+                let tally_count = tallies.len() as u64;
+                for (referee, count) in tallies {
+                    VotesCount::<T>::insert(referee, count);
+                }
+
+                StorageVersion::new(1).put::<Pallet<T>>();
+                T::DbWeight::get().reads_writes(
+                    allowed_count + vote_count + 2,
+                    allowed_count + vote_count + tally_count + 2,
+                )
+            }
+        }
+
+        /// Migrates `AllowedAccountsList` from a bare [`CallGroups`] value to the richer
+        /// [`AllowedAccountMeta`], backfilling `admitted_at` with the current block (the true
+        /// admission block is not recorded pre-migration) and `referrers` with `0`, so the
+        /// reputation-weighted priority boost in `AllowAccount::validate` starts from a clean
+        /// baseline for pre-existing entries.
+        pub struct MigrateToV2<T>(PhantomData<T>);
+
+        impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+            fn on_runtime_upgrade() -> Weight {
+                let onchain = Pallet::<T>::on_chain_storage_version();
+                if onchain >= 2 {
+                    return T::DbWeight::get().reads(1);
+                }
+
+                let now = frame_system::Pallet::<T>::block_number();
+                let entries: Vec<_> = AllowedAccounts::<T>::drain().collect();
+                let count = entries.len() as u64;
+                for (account, groups) in entries {
+                    super::AllowedAccountsList::<T>::insert(
+                        account,
+                        AllowedAccountMeta {
+                            groups,
+                            admitted_at: now,
+                            referrers: 0,
+                        },
+                    );
+                }
+
+                StorageVersion::new(2).put::<Pallet<T>>();
+                T::DbWeight::get().reads_writes(count, count)
+            }
+        }
+
+        /// Backfills the `RemovalVotesCount` O(1) tally counter introduced in V3 from the
+        /// pre-existing `RemovalVotes` storage.
+        pub struct MigrateToV3<T>(PhantomData<T>);
+
+        impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+            fn on_runtime_upgrade() -> Weight {
+                let onchain = Pallet::<T>::on_chain_storage_version();
+                if onchain >= 3 {
+                    return T::DbWeight::get().reads(1);
+                }
+
+                let mut tallies: BTreeMap<T::AccountId, u128> = BTreeMap::new();
+                let mut reads = 1u64;
+                for (referee, _referrer, ()) in RemovalVotes::<T>::iter() {
+                    reads += 1;
+                    *tallies.entry(referee).or_default() += 1;
+                }
+                let writes = tallies.len() as u64;
+                for (referee, count) in tallies {
+                    RemovalVotesCount::<T>::insert(referee, count);
+                }
+
+                StorageVersion::new(3).put::<Pallet<T>>();
+                T::DbWeight::get().reads_writes(reads, writes)
             }
         }
     }
+
     /// The following section implements the `SignedExtension` trait
     /// for the `AllowAccount` type.
     /// `SignedExtension` is being used here to filter out the not allowed accounts
@@ -219,8 +793,9 @@ pub mod pallet {
         }
 
         // Filter out the not allowed keys for predefined calls.
-        // If the key is in the allow-list, return a valid transaction,
-        // else return a custom error.
+        // If the key is in the allow-list and holds the call's group, return a valid
+        // transaction tagged and prioritised by the signer's standing, else return a custom
+        // error. Calls outside of any group are left alone.
         fn validate(
             &self,
             who: &Self::AccountId,
@@ -228,16 +803,42 @@ pub mod pallet {
             info: &DispatchInfoOf<Self::Call>,
             _len: usize,
         ) -> TransactionValidity {
-            if T::CallsToFilter::matches(call) && !<AllowedAccounts<T>>::contains_key(who) {
-                Err(InvalidTransaction::BadSigner.into())
-            } else {
-                Ok(ValidTransaction {
-                    priority: info.weight.ref_time(),
-                    longevity: TransactionLongevity::max_value(),
-                    propagate: true,
-                    ..Default::default()
-                })
-            }
+            let group = match T::CallsToFilter::group(call) {
+                None => {
+                    return Ok(ValidTransaction {
+                        priority: info.weight.ref_time(),
+                        longevity: TransactionLongevity::max_value(),
+                        propagate: true,
+                        ..Default::default()
+                    })
+                }
+                Some(group) => group,
+            };
+
+            let meta = match <AllowedAccountsList<T>>::get(who)
+                .filter(|meta| meta.groups.contains(group))
+            {
+                Some(meta) => meta,
+                None => return Err(InvalidTransaction::BadSigner.into()),
+            };
+
+            // Boost priority for longer-standing, more-vouched-for accounts, so they are served
+            // ahead of freshly (and more thinly) admitted ones under congestion.
+            let now = <frame_system::Pallet<T>>::block_number();
+            let age: u64 = now.saturating_sub(meta.admitted_at).unique_saturated_into();
+            let priority = info
+                .weight
+                .ref_time()
+                .saturating_add(age)
+                .saturating_add(meta.referrers as u64);
+
+            Ok(ValidTransaction {
+                priority,
+                provides: vec![who.encode()],
+                longevity: TransactionLongevity::max_value(),
+                propagate: true,
+                ..Default::default()
+            })
         }
 
         fn pre_dispatch(
@@ -1,9 +1,56 @@
 mod mock;
 
-use crate::mock::{test, AccountFilter, RuntimeOrigin, Test, VotesToAllow, BLOCKED_CALL};
-use frame_support::{assert_noop, assert_ok, dispatch::DispatchInfo, pallet_prelude::*};
+use crate::mock::{
+    test, AccountFilter, RuntimeOrigin, Test, VoteTtl, VotesToAllow, BLOCKED_CALL,
+    UNVERIFIED_ACCOUNT,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::DispatchInfo,
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
 use sp_runtime::traits::SignedExtension;
-use substrate_account_filter::{Error, Event};
+use substrate_account_filter::{migrations, Error, Event};
+
+// Mirrors the V1 (pre-V2) shape of `AllowedAccountsList` from
+// `substrate_account_filter::migrations`. `#[storage_alias]` hashes the type's own name into the
+// trie key, so naming this `AllowedAccounts` too — matching the `storage_prefix` pinned on the
+// real item — is what makes it land on the same storage as the real item.
+#[frame_support::storage_alias]
+type AllowedAccounts<T: substrate_account_filter::Config> = StorageMap<
+    substrate_account_filter::Pallet<T>,
+    Blake2_128Concat,
+    <T as frame_system::Config>::AccountId,
+    substrate_account_filter::CallGroups,
+>;
+
+// Mirrors the true pre-chunk0-4 (`v0`) shapes: membership-only `AllowedAccounts` and
+// presence-only `Votes`, with no call groups or cast-at block recorded at all. Wrapped in a
+// module of their own so the aliases can reuse the `AllowedAccounts`/`Votes` names without
+// colliding with the V1-shaped aliases above — only the alias's own name matters for the trie
+// key, not the module path it lives in.
+mod v0 {
+    use super::*;
+
+    #[frame_support::storage_alias]
+    pub(super) type AllowedAccounts<T: substrate_account_filter::Config> = StorageMap<
+        substrate_account_filter::Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        (),
+    >;
+
+    #[frame_support::storage_alias]
+    pub(super) type Votes<T: substrate_account_filter::Config> = StorageDoubleMap<
+        substrate_account_filter::Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        (),
+    >;
+}
 
 #[test]
 fn default_test() {
@@ -81,7 +128,7 @@ fn complexity_growth_as_allowed_account_grow() {
             let accounts = initial_accounts + i;
             assert_eq!(
                 accounts as u128,
-                substrate_account_filter::AllowedAccounts::<Test>::get(),
+                substrate_account_filter::AllowedAccountsCount::<Test>::get(),
             );
             assert_eq!(AccountFilter::allowed_accounts_list(account_to_add), None);
             let votes_required = VotesToAllow::get().mul_ceil(accounts);
@@ -206,6 +253,97 @@ fn send_success_after_adding_account() {
     });
 }
 
+#[test]
+fn one_vote_is_not_enough_to_remove_account() {
+    test().execute_with(|| {
+        assert!(AccountFilter::allowed_accounts_list(2u64).is_some());
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            2
+        ));
+        mock::System::assert_has_event(
+            Event::AccountRemovalVoted {
+                referrer: 1u64,
+                referee: 2u64,
+            }
+            .into(),
+        );
+
+        assert!(AccountFilter::allowed_accounts_list(2u64).is_some());
+    });
+}
+
+#[test]
+fn test_removing_from_allowlist() {
+    test().execute_with(|| {
+        assert!(AccountFilter::allowed_accounts_list(3u64).is_some());
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            3
+        ));
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(2),
+            3
+        ));
+
+        mock::System::assert_has_event(
+            Event::AccountRemoved {
+                account: 3u64,
+                voted_for: vec![1u64, 2u64],
+            }
+            .into(),
+        );
+
+        assert!(AccountFilter::allowed_accounts_list(3u64).is_none());
+    });
+}
+
+#[test]
+fn cannot_vote_to_remove_an_account_that_is_not_allowed() {
+    test().execute_with(|| {
+        assert_eq!(AccountFilter::allowed_accounts_list(4u64), None);
+        assert_noop!(
+            AccountFilter::vote_to_remove_account(RuntimeOrigin::signed(1), 4),
+            Error::<Test>::NotAllowed
+        );
+    });
+}
+
+#[test]
+fn duplicate_removal_vote_failure() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            2
+        ));
+        assert_noop!(
+            AccountFilter::vote_to_remove_account(RuntimeOrigin::signed(1), 2),
+            Error::<Test>::DuplicateRemovalVote
+        );
+    });
+}
+
+#[test]
+fn cannot_remove_accounts_below_minimum_quorum() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            2
+        ));
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(3),
+            2
+        ));
+        assert!(AccountFilter::allowed_accounts_list(2u64).is_none());
+
+        // Only 1 and 3 are left; removing either would go below the configured minimum.
+        assert_noop!(
+            AccountFilter::vote_to_remove_account(RuntimeOrigin::signed(1), 3),
+            Error::<Test>::BelowMinimumQuorum
+        );
+    });
+}
+
 #[test]
 fn not_blocked_call_should_be_usable_by_any() {
     let mut ext = test();
@@ -228,3 +366,311 @@ fn not_blocked_call_should_be_usable_by_any() {
         );
     });
 }
+
+#[test]
+fn expired_votes_are_not_counted_towards_the_tally() {
+    test().execute_with(|| {
+        assert_eq!(AccountFilter::allowed_accounts_list(4u64), None);
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+
+        mock::System::set_block_number(mock::System::block_number() + VoteTtl::get() + 1);
+
+        // The vote from account 1 has aged out, so this is still the first live vote for 4,
+        // not enough on its own to cross the threshold.
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 4));
+        assert!(AccountFilter::allowed_accounts_list(4u64).is_none());
+    });
+}
+
+#[test]
+fn expired_vote_can_be_recast_by_the_same_referrer() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+
+        mock::System::set_block_number(mock::System::block_number() + VoteTtl::get() + 1);
+
+        // Re-voting is allowed once the previous vote has expired.
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+    });
+}
+
+#[test]
+fn on_idle_prunes_expired_votes() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert!(substrate_account_filter::Votes::<Test>::contains_key(4u64, 1u64));
+
+        let later = mock::System::block_number() + VoteTtl::get() + 1;
+        mock::System::set_block_number(later);
+        AccountFilter::on_idle(later, frame_support::weights::Weight::MAX);
+
+        assert!(!substrate_account_filter::Votes::<Test>::contains_key(
+            4u64, 1u64
+        ));
+    });
+}
+
+#[test]
+fn on_idle_respects_the_proof_size_budget_even_with_ref_time_to_spare() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert!(substrate_account_filter::Votes::<Test>::contains_key(4u64, 1u64));
+
+        let later = mock::System::block_number() + VoteTtl::get() + 1;
+        mock::System::set_block_number(later);
+        // Plenty of ref_time, but no proof_size at all: a weight-v2-aware budget must not prune
+        // anything here, even though a ref_time-only check would let it through.
+        AccountFilter::on_idle(later, frame_support::weights::Weight::from_parts(u64::MAX, 0));
+
+        assert!(substrate_account_filter::Votes::<Test>::contains_key(
+            4u64, 1u64
+        ));
+    });
+}
+
+#[test]
+fn invalid_call_group_rejected() {
+    test().execute_with(|| {
+        assert_noop!(
+            AccountFilter::vote_for_account_in_groups(RuntimeOrigin::signed(1), 4, vec![64]),
+            Error::<Test>::InvalidCallGroup
+        );
+    });
+}
+
+#[test]
+fn scoped_vote_grants_union_of_requested_groups() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account_in_groups(
+            RuntimeOrigin::signed(1),
+            4,
+            vec![0]
+        ));
+        assert_ok!(AccountFilter::vote_for_account_in_groups(
+            RuntimeOrigin::signed(2),
+            4,
+            vec![1]
+        ));
+
+        let meta = AccountFilter::allowed_accounts_list(4u64).unwrap();
+        assert!(meta.groups.contains(0));
+        assert!(meta.groups.contains(1));
+        assert!(!meta.groups.contains(2));
+    });
+}
+
+#[test]
+fn full_access_vote_still_grants_every_group() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 4));
+
+        let meta = AccountFilter::allowed_accounts_list(4u64).unwrap();
+        assert!(meta.groups.contains(0));
+        assert!(meta.groups.contains(63));
+    });
+}
+
+#[test]
+fn allowed_accounts_counter_stays_consistent_across_add_and_remove() {
+    test().execute_with(|| {
+        assert_eq!(AccountFilter::allowed_accounts(), 3u128);
+
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 4));
+        assert_eq!(AccountFilter::allowed_accounts(), 4u128);
+        assert_eq!(
+            AccountFilter::allowed_accounts() as usize,
+            substrate_account_filter::AllowedAccountsList::<Test>::iter().count()
+        );
+
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            4
+        ));
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(2),
+            4
+        ));
+        assert_eq!(AccountFilter::allowed_accounts(), 3u128);
+        assert_eq!(
+            AccountFilter::allowed_accounts() as usize,
+            substrate_account_filter::AllowedAccountsList::<Test>::iter().count()
+        );
+    });
+}
+
+#[test]
+fn votes_counter_stays_consistent_across_votes_admission_and_expiry() {
+    test().execute_with(|| {
+        assert_eq!(AccountFilter::votes_for_account(4u64), None);
+
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert_eq!(AccountFilter::votes_for_account(4u64).unwrap(), 1u128);
+        assert_eq!(
+            AccountFilter::votes_for_account(4u64).unwrap() as usize,
+            substrate_account_filter::Votes::<Test>::iter_prefix(4u64).count()
+        );
+
+        // Crossing the threshold drains the tally entirely.
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 4));
+        assert_eq!(AccountFilter::votes_for_account(4u64), None);
+
+        // A fresh vote that then expires is also reflected in the counter.
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 5));
+        assert_eq!(AccountFilter::votes_for_account(5u64).unwrap(), 1u128);
+
+        mock::System::set_block_number(mock::System::block_number() + VoteTtl::get() + 1);
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 5));
+        // Account 1's vote expired, so this is still the only live vote for 5.
+        assert_eq!(AccountFilter::votes_for_account(5u64).unwrap(), 1u128);
+    });
+}
+
+#[test]
+fn unverified_account_admission_is_held_back_pending_verification() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(
+            RuntimeOrigin::signed(1),
+            UNVERIFIED_ACCOUNT
+        ));
+        assert_ok!(AccountFilter::vote_for_account(
+            RuntimeOrigin::signed(2),
+            UNVERIFIED_ACCOUNT
+        ));
+
+        // Enough votes were cast, but the identity provider never verified this account, so it
+        // is not admitted.
+        assert!(AccountFilter::allowed_accounts_list(UNVERIFIED_ACCOUNT).is_none());
+        assert_eq!(
+            AccountFilter::votes_for_account(UNVERIFIED_ACCOUNT).unwrap(),
+            2u128
+        );
+
+        mock::System::assert_has_event(
+            Event::<Test>::AccountAdmissionPendingVerification {
+                account: UNVERIFIED_ACCOUNT,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn verified_account_is_admitted_once_votes_cross_threshold() {
+    test().execute_with(|| {
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(1), 4));
+        assert_ok!(AccountFilter::vote_for_account(RuntimeOrigin::signed(2), 4));
+
+        assert!(AccountFilter::allowed_accounts_list(4u64).is_some());
+    });
+}
+
+#[test]
+fn longer_standing_account_gets_higher_priority() {
+    test().execute_with(|| {
+        let info = DispatchInfo::default();
+        let len = 0_usize;
+
+        let priority_at_admission = substrate_account_filter::AllowAccount::<Test>::new()
+            .validate(&2, BLOCKED_CALL, &info, len)
+            .unwrap()
+            .priority;
+
+        mock::System::set_block_number(mock::System::block_number() + 100);
+
+        let priority_later = substrate_account_filter::AllowAccount::<Test>::new()
+            .validate(&2, BLOCKED_CALL, &info, len)
+            .unwrap()
+            .priority;
+
+        assert!(priority_later > priority_at_admission);
+    });
+}
+
+#[test]
+fn migrate_to_v1_backfills_from_the_true_pre_existing_schema() {
+    test().execute_with(|| {
+        // Pretend this is a genuinely pre-V1 chain: no call groups, no cast-at block, and none
+        // of the O(1) tallies populated — just bare membership/presence, the shape this pallet
+        // actually had before chunk0-2/chunk0-3/chunk0-4 ever introduced a richer value type.
+        StorageVersion::new(0).put::<AccountFilter>();
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(1);
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(2);
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(3);
+        substrate_account_filter::AllowedAccountsCount::<Test>::kill();
+        let _ = substrate_account_filter::VotesCount::<Test>::clear(u32::MAX, None);
+
+        v0::AllowedAccounts::<Test>::insert(1u64, ());
+        v0::AllowedAccounts::<Test>::insert(2u64, ());
+        v0::Votes::<Test>::insert(5u64, 1u64, ());
+
+        mock::System::set_block_number(7);
+
+        migrations::MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(AccountFilter::on_chain_storage_version(), 1);
+        assert_eq!(AccountFilter::allowed_accounts(), 2u128);
+        assert_eq!(
+            AllowedAccounts::<Test>::get(1u64),
+            Some(substrate_account_filter::CallGroups::all())
+        );
+        assert_eq!(
+            AllowedAccounts::<Test>::get(2u64),
+            Some(substrate_account_filter::CallGroups::all())
+        );
+
+        let vote = substrate_account_filter::Votes::<Test>::get(5u64, 1u64)
+            .expect("vote migrated to the V1 shape");
+        assert_eq!(vote.cast_at, 7);
+        assert_eq!(vote.groups, substrate_account_filter::CallGroups::all());
+        assert_eq!(AccountFilter::votes_for_account(5u64).unwrap(), 1u128);
+    });
+}
+
+#[test]
+fn migrate_to_v2_preserves_pre_existing_entries_under_the_new_shape() {
+    test().execute_with(|| {
+        // The genesis-seeded accounts are already written in the post-V2 `AllowedAccountMeta`
+        // shape, so clear them and seed a lone pre-migration `CallGroups` entry through the
+        // alias instead, to exercise exactly the bytes `MigrateToV2::drain()` sees on a real
+        // pre-V2 chain.
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(1);
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(2);
+        substrate_account_filter::AllowedAccountsList::<Test>::remove(3);
+
+        let pre_migration_groups = substrate_account_filter::CallGroups::from_groups(&[1, 3]);
+        AllowedAccounts::<Test>::insert(9u64, pre_migration_groups);
+
+        StorageVersion::new(1).put::<AccountFilter>();
+        mock::System::set_block_number(42);
+
+        migrations::MigrateToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(AccountFilter::on_chain_storage_version(), 2);
+        let meta = AccountFilter::allowed_accounts_list(9u64).expect("account survives migration");
+        assert_eq!(meta.groups, pre_migration_groups);
+        assert_eq!(meta.admitted_at, 42);
+        assert_eq!(meta.referrers, 0);
+    });
+}
+
+#[test]
+fn migrate_to_v3_backfills_removal_votes_counter() {
+    test().execute_with(|| {
+        // Cast a removal vote the normal way so `RemovalVotes` is genuinely populated (one vote
+        // alone is not enough to complete the removal, so the entry stays in place), then
+        // pretend this is a pre-V3 chain where the O(1) tally was never backfilled.
+        assert_ok!(AccountFilter::vote_to_remove_account(
+            RuntimeOrigin::signed(1),
+            2
+        ));
+        StorageVersion::new(2).put::<AccountFilter>();
+        let _ = substrate_account_filter::RemovalVotesCount::<Test>::clear(u32::MAX, None);
+
+        migrations::MigrateToV3::<Test>::on_runtime_upgrade();
+
+        assert_eq!(AccountFilter::on_chain_storage_version(), 3);
+        assert_eq!(AccountFilter::removal_votes_for_account(2u64).unwrap(), 1u128);
+    });
+}